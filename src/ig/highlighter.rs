@@ -0,0 +1,113 @@
+use super::entries::EntryType;
+use std::collections::HashMap;
+use std::path::Path;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{Style, Theme, ThemeSet};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+
+pub struct Highlighter {
+    syntax_set: SyntaxSet,
+    theme: Theme,
+    syntax_cache: HashMap<String, Option<SyntaxReference>>,
+}
+
+impl Highlighter {
+    pub fn new() -> Self {
+        let theme_set = ThemeSet::load_defaults();
+        Self {
+            syntax_set: SyntaxSet::load_defaults_newlines(),
+            theme: theme_set.themes["base16-ocean.dark"].clone(),
+            syntax_cache: HashMap::new(),
+        }
+    }
+
+    pub fn highlight_match(&mut self, header: &str, text: &str) -> Vec<(Style, String)> {
+        match self.syntax_for_header(header) {
+            Some(syntax) => {
+                let mut highlighter = HighlightLines::new(&syntax, &self.theme);
+                match highlighter.highlight_line(text, &self.syntax_set) {
+                    Ok(ranges) => ranges
+                        .into_iter()
+                        .map(|(style, part)| (style, part.to_owned()))
+                        .collect(),
+                    Err(_) => Self::plain(text),
+                }
+            }
+            None => Self::plain(text),
+        }
+    }
+
+    pub fn highlight_entry(&mut self, header: &str, entry: &EntryType) -> Vec<(Style, String)> {
+        match entry {
+            EntryType::Match(_, text) => self.highlight_match(header, text),
+            EntryType::Header(name) => Self::plain(name),
+        }
+    }
+
+    fn syntax_for_header(&mut self, header: &str) -> Option<SyntaxReference> {
+        if let Some(cached) = self.syntax_cache.get(header) {
+            return cached.clone();
+        }
+
+        let syntax = Path::new(header)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| self.syntax_set.find_syntax_by_extension(ext))
+            .cloned();
+
+        self.syntax_cache.insert(header.to_owned(), syntax.clone());
+        syntax
+    }
+
+    fn plain(text: &str) -> Vec<(Style, String)> {
+        vec![(Style::default(), text.to_owned())]
+    }
+}
+
+impl Default for Highlighter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_extension_resolves_syntax() {
+        let mut highlighter = Highlighter::new();
+        let spans = highlighter.highlight_match("main.rs", "fn main() {}");
+        assert!(!spans.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_extension_falls_back_to_plain() {
+        let mut highlighter = Highlighter::new();
+        let spans = highlighter.highlight_match("data.unknownext", "just some text");
+
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].1, "just some text");
+    }
+
+    #[test]
+    fn test_caches_resolved_syntax_per_header() {
+        let mut highlighter = Highlighter::new();
+        assert!(!highlighter.syntax_cache.contains_key("main.rs"));
+
+        highlighter.highlight_match("main.rs", "fn main() {}");
+        assert!(highlighter.syntax_cache.contains_key("main.rs"));
+
+        highlighter.highlight_match("main.rs", "let x = 1;");
+        assert_eq!(highlighter.syntax_cache.len(), 1);
+    }
+
+    #[test]
+    fn test_header_entry_highlights_as_plain_name() {
+        let mut highlighter = Highlighter::new();
+        let spans = highlighter.highlight_entry("main.rs", &EntryType::Header("main.rs".to_owned()));
+
+        assert_eq!(spans.len(), 1);
+        assert_eq!(spans[0].1, "main.rs");
+    }
+}
@@ -1,16 +1,45 @@
 use super::entries::{EntryType, FileEntry};
+use super::highlighter::Highlighter;
+use super::watcher::{FileChange, FileWatcher};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
 use std::cmp;
+use std::collections::HashSet;
+use syntect::highlighting::Style;
 
-#[derive(Copy, Clone, Default)]
-pub struct ListState(Option<usize>);
+#[derive(Clone, Default)]
+pub struct ListState {
+    selected: Option<usize>,
+    marked: HashSet<usize>,
+}
 
 impl ListState {
     pub fn select(&mut self, index: Option<usize>) {
-        self.0 = index;
+        self.selected = index;
     }
 
     pub fn selected(&self) -> Option<usize> {
-        self.0
+        self.selected
+    }
+
+    // Keeps `marked` in sync whenever `entries` is spliced: marks inside the
+    // replaced span no longer point at anything meaningful and are dropped,
+    // marks after it are shifted by however much the span grew or shrank.
+    fn adjust_marks_for_splice(&mut self, range: std::ops::Range<usize>, new_len: usize) {
+        let delta = new_len as isize - range.len() as isize;
+        self.marked = self
+            .marked
+            .drain()
+            .filter_map(|index| {
+                if index < range.start {
+                    Some(index)
+                } else if index < range.end {
+                    None
+                } else {
+                    Some((index as isize + delta) as usize)
+                }
+            })
+            .collect();
     }
 }
 
@@ -18,10 +47,20 @@ impl ListState {
 pub struct ResultList {
     entries: Vec<EntryType>,
     state: ListState,
+    trash_files: bool,
+    filter: Option<String>,
+    highlighter: Highlighter,
+    watcher: Option<FileWatcher>,
+    matcher: SkimMatcherV2,
 }
 
 impl ResultList {
     pub fn add_entry(&mut self, mut entry: FileEntry) {
+        if let (Some(watcher), Some(EntryType::Header(name))) = (&mut self.watcher, entry.0.first())
+        {
+            let _ = watcher.watch(name);
+        }
+
         self.entries.append(&mut entry.0);
 
         if self.state.selected().is_none() {
@@ -33,9 +72,44 @@ impl ResultList {
         self.entries.iter()
     }
 
+    pub fn set_trash_files(&mut self, trash_files: bool) {
+        self.trash_files = trash_files;
+    }
+
+    pub fn enable_file_watching(&mut self) -> notify::Result<()> {
+        let mut watcher = FileWatcher::new()?;
+        for entry in &self.entries {
+            if let EntryType::Header(name) = entry {
+                watcher.watch(name)?;
+            }
+        }
+        self.watcher = Some(watcher);
+        Ok(())
+    }
+
+    pub fn poll_file_changes(&mut self) -> Vec<FileChange> {
+        match &mut self.watcher {
+            Some(watcher) => watcher.poll(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn apply_file_change(&mut self, change: FileChange, refreshed: Option<FileEntry>) {
+        match change {
+            FileChange::Removed(name) => self.remove_file_by_name(&name),
+            FileChange::Modified(name) => match refreshed {
+                Some(new_entry) if !new_entry.0.is_empty() => {
+                    self.replace_file_entries(&name, new_entry)
+                }
+                _ => self.remove_file_by_name(&name),
+            },
+        }
+    }
+
     pub fn clear(&mut self) {
         self.entries.clear();
         self.state.select(None);
+        self.clear_marks();
     }
 
     pub fn is_empty(&self) -> bool {
@@ -47,21 +121,12 @@ impl ResultList {
             return;
         }
 
-        let index = match self.state.selected() {
-            Some(i) => {
-                if i == self.entries.len() - 1 {
-                    i
-                } else {
-                    match self.entries[i + 1] {
-                        EntryType::Header(_) => i + 2,
-                        EntryType::Match(_, _) => i + 1,
-                    }
-                }
-            }
-            None => 1,
-        };
-
-        self.state.select(Some(index));
+        let start = self.state.selected().map_or(0, |i| i + 1);
+        if let Some(index) = self.find_visible_match(start, self.entries.len()) {
+            self.state.select(Some(index));
+        } else if self.state.selected().is_none() {
+            self.select_first_visible_match();
+        }
     }
 
     pub fn previous_match(&mut self) {
@@ -69,21 +134,14 @@ impl ResultList {
             return;
         }
 
-        let index = match self.state.selected() {
+        match self.state.selected() {
             Some(i) => {
-                if i == 1 {
-                    1
-                } else {
-                    match self.entries[i - 1] {
-                        EntryType::Header(_) => i - 2,
-                        EntryType::Match(_, _) => i - 1,
-                    }
+                if let Some(index) = self.find_visible_match_rev(0, i) {
+                    self.state.select(Some(index));
                 }
             }
-            None => 1,
-        };
-
-        self.state.select(Some(index));
+            None => self.select_first_visible_match(),
+        }
     }
 
     pub fn next_file(&mut self) {
@@ -91,30 +149,24 @@ impl ResultList {
             return;
         }
 
-        let index = match self.state.selected() {
-            Some(i) => {
-                let mut next_index = i;
-                loop {
-                    if next_index == self.entries.len() - 1 {
-                        next_index = i;
-                        break;
-                    }
+        let start = self.state.selected().map_or(0, |i| i + 1);
 
-                    next_index += 1;
-                    match self.entries[next_index] {
-                        EntryType::Header(_) => {
-                            next_index += 1;
-                            break;
-                        }
-                        EntryType::Match(_, _) => continue,
-                    }
-                }
-                next_index
-            }
-            None => 1,
+        let mut header_index = start;
+        while header_index < self.entries.len() && !self.is_header_visible(header_index) {
+            header_index += 1;
+        }
+
+        let target = if header_index < self.entries.len() {
+            self.find_visible_match(header_index + 1, self.entries.len())
+        } else {
+            None
         };
 
-        self.state.select(Some(index));
+        match target {
+            Some(index) => self.state.select(Some(index)),
+            None if self.state.selected().is_none() => self.select_first_visible_match(),
+            None => (),
+        }
     }
 
     pub fn previous_file(&mut self) {
@@ -122,35 +174,32 @@ impl ResultList {
             return;
         }
 
-        let index = match self.state.selected() {
-            Some(i) => {
-                let mut next_index = i;
-                let mut first_header_visited = false;
-                loop {
-                    if next_index == 1 {
-                        break;
-                    }
+        let current_index = match self.state.selected() {
+            Some(i) => i,
+            None => return self.select_first_visible_match(),
+        };
 
-                    next_index -= 1;
-                    match self.entries[next_index] {
-                        EntryType::Header(_) => {
-                            if !first_header_visited {
-                                first_header_visited = true;
-                                next_index -= 1;
-                            } else {
-                                next_index += 1;
-                                break;
-                            }
-                        }
-                        EntryType::Match(_, _) => continue,
-                    }
-                }
-                next_index
+        let mut current_header = 0;
+        for index in (0..current_index).rev() {
+            if self.is_header_visible(index) {
+                current_header = index;
+                break;
             }
-            None => 1,
-        };
+        }
 
-        self.state.select(Some(index));
+        let mut previous_header = None;
+        for index in (0..current_header).rev() {
+            if self.is_header_visible(index) {
+                previous_header = Some(index);
+                break;
+            }
+        }
+
+        if let Some(header_index) = previous_header {
+            if let Some(index) = self.find_visible_match(header_index + 1, self.entries.len()) {
+                self.state.select(Some(index));
+            }
+        }
     }
 
     pub fn top(&mut self) {
@@ -158,7 +207,10 @@ impl ResultList {
             return;
         }
 
-        self.state.select(Some(1));
+        match self.find_visible_match(0, self.entries.len()) {
+            Some(index) => self.state.select(Some(index)),
+            None => self.state.select(None),
+        }
     }
 
     pub fn bottom(&mut self) {
@@ -166,27 +218,49 @@ impl ResultList {
             return;
         }
 
-        self.state.select(Some(self.entries.len() - 1));
+        match self.find_visible_match_rev(0, self.entries.len()) {
+            Some(index) => self.state.select(Some(index)),
+            None => self.state.select(None),
+        }
     }
 
-    pub fn remove_current_entry(&mut self) {
-        if self.is_empty() {
+    pub fn set_filter(&mut self, query: &str) {
+        if query.is_empty() {
+            self.clear_filter();
+            return;
+        }
+
+        self.filter = Some(query.to_owned());
+        self.fix_selection_after_filter();
+    }
+
+    pub fn clear_filter(&mut self) {
+        if self.filter.is_none() {
             return;
         }
 
+        self.filter = None;
+        self.fix_selection_after_filter();
+    }
+
+    pub fn remove_current_entry(&mut self) -> trash::Result<()> {
+        if self.state.selected().is_none() {
+            return Ok(());
+        }
+
         if self.is_last_match_in_file() {
-            self.remove_current_file();
+            self.remove_current_file()
         } else {
             self.remove_current_entry_and_select_previous();
+            Ok(())
         }
     }
 
-    pub fn remove_current_file(&mut self) {
-        if self.is_empty() {
-            return;
-        }
-
-        let selected_index = self.state.selected().unwrap();
+    pub fn remove_current_file(&mut self) -> trash::Result<()> {
+        let selected_index = match self.state.selected() {
+            Some(index) => index,
+            None => return Ok(()),
+        };
 
         let mut current_file_header_index = 0;
         for index in (0..selected_index).rev() {
@@ -204,7 +278,22 @@ impl ResultList {
             }
         }
 
+        let header_name = match &self.entries[current_file_header_index] {
+            EntryType::Header(name) => name.clone(),
+            EntryType::Match(_, _) => unreachable!(),
+        };
+
+        if self.trash_files {
+            trash::delete(&header_name)?;
+        }
+
+        if let Some(watcher) = &mut self.watcher {
+            let _ = watcher.unwatch(&header_name);
+        }
+
         let span = next_file_header_index - current_file_header_index;
+        self.state
+            .adjust_marks_for_splice(current_file_header_index..next_file_header_index, 0);
         for _ in 0..span {
             self.entries.remove(current_file_header_index);
         }
@@ -218,6 +307,83 @@ impl ResultList {
                     1,
                 )));
             }
+            self.fix_selection_after_filter();
+        }
+
+        Ok(())
+    }
+
+    pub fn replace_file_entries(&mut self, name: &str, mut new: FileEntry) {
+        let mut header_index = None;
+        for (index, entry) in self.entries.iter().enumerate() {
+            if let EntryType::Header(header_name) = entry {
+                if header_name == name {
+                    header_index = Some(index);
+                    break;
+                }
+            }
+        }
+
+        let header_index = match header_index {
+            Some(index) => index,
+            None => return,
+        };
+
+        let mut next_header_index = self.entries.len();
+        for index in (header_index + 1)..self.entries.len() {
+            if self.is_header(index) {
+                next_header_index = index;
+                break;
+            }
+        }
+
+        let old_span = next_header_index - header_index;
+        // A file with no remaining matches shouldn't leave a dangling,
+        // match-less header block behind.
+        if new.0.len() <= 1 {
+            new.0.clear();
+        }
+        let new_span = new.0.len();
+        let selected = self.state.selected();
+        let offset_in_span = selected
+            .filter(|&i| i >= header_index && i < next_header_index)
+            .map(|i| i - header_index);
+
+        self.state
+            .adjust_marks_for_splice(header_index..next_header_index, new_span);
+        self.entries
+            .splice(header_index..next_header_index, new.0.drain(..));
+
+        let new_selected = match (selected, offset_in_span) {
+            (_, Some(offset)) if new_span > 0 => {
+                Some(header_index + cmp::max(1, cmp::min(offset, new_span - 1)))
+            }
+            (_, Some(_)) => None,
+            (Some(i), None) if i >= next_header_index => Some(if new_span >= old_span {
+                i + (new_span - old_span)
+            } else {
+                i - (old_span - new_span)
+            }),
+            (Some(i), None) => Some(i),
+            (None, None) => None,
+        };
+
+        self.state.select(new_selected);
+        self.fix_selection_after_filter();
+    }
+
+    pub fn remove_file_by_name(&mut self, name: &str) {
+        for (index, entry) in self.entries.iter().enumerate() {
+            if let EntryType::Header(header_name) = entry {
+                if header_name == name {
+                    self.state.select(Some(index + 1));
+                    // This drops the file from the list only; never trash it here.
+                    let trash_files = std::mem::replace(&mut self.trash_files, false);
+                    let _ = self.remove_current_file();
+                    self.trash_files = trash_files;
+                    return;
+                }
+            }
         }
     }
 
@@ -225,6 +391,98 @@ impl ResultList {
         matches!(self.entries[index], EntryType::Header(_))
     }
 
+    fn is_match_visible(&self, index: usize, header: &str) -> bool {
+        match &self.entries[index] {
+            EntryType::Match(_, text) => match &self.filter {
+                Some(query) => {
+                    self.matcher.fuzzy_match(text, query).is_some()
+                        || self.matcher.fuzzy_match(header, query).is_some()
+                }
+                None => true,
+            },
+            EntryType::Header(_) => false,
+        }
+    }
+
+    fn is_header_visible(&self, index: usize) -> bool {
+        if !self.is_header(index) {
+            return false;
+        }
+
+        if self.filter.is_none() {
+            return true;
+        }
+
+        let header = match &self.entries[index] {
+            EntryType::Header(name) => name.as_str(),
+            EntryType::Match(_, _) => unreachable!(),
+        };
+
+        let mut match_index = index + 1;
+        while match_index < self.entries.len() && !self.is_header(match_index) {
+            if self.is_match_visible(match_index, header) {
+                return true;
+            }
+            match_index += 1;
+        }
+
+        false
+    }
+
+    fn owning_header_name(&self, index: usize) -> &str {
+        for i in (0..=index).rev() {
+            if let EntryType::Header(name) = &self.entries[i] {
+                return name;
+            }
+        }
+
+        unreachable!("every match is preceded by a header")
+    }
+
+    // Resolves the owning header of every entry in a single forward pass, so
+    // callers that need it for a whole range don't each re-scan backward
+    // from their own index (which made filtering quadratic in match count).
+    fn owning_headers(&self) -> Vec<&str> {
+        let mut current = "";
+        let mut headers = Vec::with_capacity(self.entries.len());
+        for entry in &self.entries {
+            if let EntryType::Header(name) = entry {
+                current = name;
+            }
+            headers.push(current);
+        }
+        headers
+    }
+
+    fn find_visible_match(&self, from: usize, to: usize) -> Option<usize> {
+        let headers = self.owning_headers();
+        (from..to).find(|&i| self.is_match_visible(i, headers[i]))
+    }
+
+    fn find_visible_match_rev(&self, from: usize, to: usize) -> Option<usize> {
+        let headers = self.owning_headers();
+        (from..to).rev().find(|&i| self.is_match_visible(i, headers[i]))
+    }
+
+    fn select_first_visible_match(&mut self) {
+        let index = self.find_visible_match(0, self.entries.len());
+        self.state.select(index);
+    }
+
+    fn fix_selection_after_filter(&mut self) {
+        let selected = self.state.selected();
+
+        if selected.is_some_and(|i| self.is_match_visible(i, self.owning_header_name(i))) {
+            return;
+        }
+
+        let fallback = selected.and_then(|i| self.find_visible_match_rev(0, i));
+        match fallback.or_else(|| self.find_visible_match(0, self.entries.len())) {
+            Some(index) => self.state.select(Some(index)),
+            None => self.state.select(None),
+        }
+    }
+
     fn is_last_match_in_file(&self) -> bool {
         let current_index = self.state.selected().unwrap();
         if self.is_header(current_index - 1) {
@@ -240,10 +498,13 @@ impl ResultList {
 
     fn remove_current_entry_and_select_previous(&mut self) {
         let selected_index = self.state.selected().unwrap();
+        self.state
+            .adjust_marks_for_splice(selected_index..selected_index + 1, 0);
         self.entries.remove(selected_index);
         if selected_index >= self.entries.len() || self.is_header(selected_index) {
             self.state.select(Some(selected_index - 1));
         }
+        self.fix_selection_after_filter();
     }
 
     pub fn get_selected_entry(&self) -> Option<(&str, u64)> {
@@ -268,17 +529,87 @@ impl ResultList {
         }
     }
 
+    pub fn get_selected_spans(&mut self) -> Option<Vec<(Style, String)>> {
+        let index = self.state.selected()?;
+        if matches!(self.entries[index], EntryType::Header(_)) {
+            return None;
+        }
+
+        let header = self.owning_header_name(index).to_owned();
+        Some(self.highlighter.highlight_entry(&header, &self.entries[index]))
+    }
+
+    pub fn iter_spans(&mut self) -> Vec<Vec<(Style, String)>> {
+        let mut current_header = String::new();
+        let mut spans = Vec::with_capacity(self.entries.len());
+
+        for index in 0..self.entries.len() {
+            if let EntryType::Header(name) = &self.entries[index] {
+                current_header = name.clone();
+            }
+
+            spans.push(
+                self.highlighter
+                    .highlight_entry(&current_header, &self.entries[index]),
+            );
+        }
+
+        spans
+    }
+
     pub fn get_state(&self) -> ListState {
-        self.state
+        self.state.clone()
+    }
+
+    pub fn toggle_mark_current(&mut self) {
+        if let Some(index) = self.state.selected() {
+            if self.is_header(index) {
+                return;
+            }
+
+            if !self.state.marked.remove(&index) {
+                self.state.marked.insert(index);
+            }
+        }
+    }
+
+    pub fn marked_indices(&self) -> &HashSet<usize> {
+        &self.state.marked
+    }
+
+    pub fn clear_marks(&mut self) {
+        self.state.marked.clear();
+    }
+
+    pub fn remove_marked(&mut self) -> trash::Result<()> {
+        if self.state.marked.is_empty() {
+            return Ok(());
+        }
+
+        let mut indices: Vec<usize> = self.state.marked.iter().copied().collect();
+        indices.sort_unstable_by(|a, b| b.cmp(a));
+
+        for index in indices {
+            // Skip an index whose mark is no longer set; each removal drops
+            // the removed entry's own mark.
+            if !self.state.marked.contains(&index) {
+                continue;
+            }
+
+            self.state.select(Some(index));
+            self.remove_current_entry()?;
+            self.state.marked.remove(&index);
+        }
+
+        Ok(())
     }
 
     pub fn get_current_match_index(&self) -> usize {
         match self.state.selected() {
             Some(selected) => {
-                self.entries
-                    .iter()
-                    .take(selected)
-                    .filter(|&e| matches!(e, EntryType::Match(_, _)))
+                let headers = self.owning_headers();
+                (0..selected)
+                    .filter(|&index| self.is_match_visible(index, headers[index]))
                     .count()
                     + 1
             }
@@ -287,16 +618,15 @@ impl ResultList {
     }
 
     pub fn get_number_of_matches(&self) -> usize {
-        self.entries
-            .iter()
-            .filter(|&e| matches!(e, EntryType::Match(_, _)))
+        let headers = self.owning_headers();
+        (0..self.entries.len())
+            .filter(|&index| self.is_match_visible(index, headers[index]))
             .count()
     }
 
     pub fn get_number_of_file_entries(&self) -> usize {
-        self.entries
-            .iter()
-            .filter(|&e| matches!(e, EntryType::Header(_)))
+        (0..self.entries.len())
+            .filter(|&index| self.is_header_visible(index))
             .count()
     }
 }
@@ -305,6 +635,7 @@ impl ResultList {
 mod tests {
     use super::*;
     use crate::ig::entries::Match;
+    use std::{fs, thread::sleep, time::Duration};
 
     #[test]
     fn test_empty_list() {
@@ -330,4 +661,242 @@ mod tests {
         assert_eq!(list.entries.len(), 5);
         assert_eq!(list.state.selected(), Some(1));
     }
+
+    #[test]
+    fn test_toggle_mark_current() {
+        let mut list = ResultList::default();
+        list.add_entry(FileEntry::new(
+            "entry1",
+            vec![Match::new(0, "e1m1"), Match::new(0, "e1m2")],
+        ));
+
+        list.toggle_mark_current();
+        assert!(list.marked_indices().contains(&1));
+
+        list.toggle_mark_current();
+        assert!(list.marked_indices().is_empty());
+
+        list.next_match();
+        list.toggle_mark_current();
+        assert!(list.marked_indices().contains(&2));
+
+        list.clear_marks();
+        assert!(list.marked_indices().is_empty());
+    }
+
+    #[test]
+    fn test_remove_marked() {
+        let mut list = ResultList::default();
+        list.add_entry(FileEntry::new(
+            "entry1",
+            vec![Match::new(0, "e1m1"), Match::new(0, "e1m2")],
+        ));
+        list.add_entry(FileEntry::new("entry2", vec![Match::new(0, "e2m1")]));
+
+        list.state.select(Some(1));
+        list.toggle_mark_current();
+        list.state.select(Some(4));
+        list.toggle_mark_current();
+
+        list.remove_marked().unwrap();
+
+        assert_eq!(list.entries.len(), 2);
+        assert!(list.marked_indices().is_empty());
+    }
+
+    #[test]
+    fn test_removing_an_unmarked_entry_renumbers_other_marks() {
+        let mut list = ResultList::default();
+        list.add_entry(FileEntry::new(
+            "entry1",
+            vec![Match::new(0, "e1m1"), Match::new(0, "e1m2")],
+        ));
+        list.add_entry(FileEntry::new("entry2", vec![Match::new(0, "e2m1")]));
+
+        list.state.select(Some(4));
+        list.toggle_mark_current();
+
+        // Remove an unrelated, unmarked entry earlier in the list.
+        list.state.select(Some(1));
+        list.remove_current_entry().unwrap();
+
+        assert_eq!(list.entries.len(), 4);
+        assert_eq!(list.get_selected_entry().unwrap().0, "entry1");
+
+        // The mark must have shifted down with its entry, not dangle at a
+        // now out-of-range (or silently wrong) index.
+        list.remove_marked().unwrap();
+        assert_eq!(list.entries.len(), 2);
+        assert!(list.marked_indices().is_empty());
+    }
+
+    #[test]
+    fn test_clear_resets_marks() {
+        let mut list = ResultList::default();
+        list.add_entry(FileEntry::new("entry1", vec![Match::new(0, "e1m1")]));
+        list.toggle_mark_current();
+        assert!(!list.marked_indices().is_empty());
+
+        list.clear();
+
+        assert!(list.marked_indices().is_empty());
+    }
+
+    #[test]
+    fn test_trash_files_disabled_by_default() {
+        let mut list = ResultList::default();
+        list.add_entry(FileEntry::new("entry1", vec![Match::new(0, "e1m1")]));
+
+        list.state.select(Some(1));
+        list.remove_current_entry().unwrap();
+
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn test_replace_file_entries() {
+        let mut list = ResultList::default();
+        list.add_entry(FileEntry::new("entry1", vec![Match::new(0, "e1m1")]));
+        list.add_entry(FileEntry::new(
+            "entry2",
+            vec![Match::new(0, "e2m1"), Match::new(0, "e2m2")],
+        ));
+        list.state.select(Some(3));
+
+        list.replace_file_entries(
+            "entry2",
+            FileEntry::new("entry2", vec![Match::new(0, "e2m1-new")]),
+        );
+
+        assert_eq!(list.entries.len(), 4);
+        assert_eq!(list.state.selected(), Some(3));
+    }
+
+    #[test]
+    fn test_replace_file_entries_drops_header_with_no_remaining_matches() {
+        let mut list = ResultList::default();
+        list.add_entry(FileEntry::new("entry1", vec![Match::new(0, "e1m1")]));
+        list.add_entry(FileEntry::new("entry2", vec![Match::new(0, "e2m1")]));
+        list.state.select(Some(3));
+
+        list.replace_file_entries("entry2", FileEntry::new("entry2", vec![]));
+
+        assert_eq!(list.entries.len(), 2);
+        assert_eq!(list.get_selected_entry().unwrap().0, "entry1");
+        assert!(!matches!(
+            list.state.selected().map(|i| &list.entries[i]),
+            Some(EntryType::Header(_))
+        ));
+    }
+
+    #[test]
+    fn test_remove_file_by_name() {
+        let mut list = ResultList::default();
+        list.add_entry(FileEntry::new("entry1", vec![Match::new(0, "e1m1")]));
+        list.add_entry(FileEntry::new("entry2", vec![Match::new(0, "e2m1")]));
+
+        list.remove_file_by_name("entry1");
+
+        assert_eq!(list.entries.len(), 2);
+        assert_eq!(list.get_selected_entry().unwrap().0, "entry2");
+    }
+
+    #[test]
+    fn test_set_and_clear_filter() {
+        let mut list = ResultList::default();
+        list.add_entry(FileEntry::new(
+            "entry1",
+            vec![Match::new(0, "apple"), Match::new(0, "banana")],
+        ));
+        list.add_entry(FileEntry::new("entry2", vec![Match::new(0, "cherry")]));
+
+        list.set_filter("banana");
+
+        assert_eq!(list.get_number_of_matches(), 1);
+        assert_eq!(list.get_number_of_file_entries(), 1);
+        assert_eq!(list.get_selected_entry().unwrap().1, 0);
+        assert_eq!(list.entries.len(), 5);
+
+        list.clear_filter();
+
+        assert_eq!(list.get_number_of_matches(), 3);
+        assert_eq!(list.get_number_of_file_entries(), 2);
+    }
+
+    #[test]
+    fn test_navigation_respects_filter() {
+        let mut list = ResultList::default();
+        list.add_entry(FileEntry::new(
+            "entry1",
+            vec![Match::new(0, "apple"), Match::new(0, "banana")],
+        ));
+        list.add_entry(FileEntry::new(
+            "entry2",
+            vec![Match::new(0, "apple2"), Match::new(0, "banana2")],
+        ));
+
+        list.set_filter("apple");
+        list.top();
+
+        let (name, _) = list.get_selected_entry().unwrap();
+        assert_eq!(name, "entry1");
+
+        list.next_match();
+        let (name, _) = list.get_selected_entry().unwrap();
+        assert_eq!(name, "entry2");
+
+        list.next_match();
+        let (name, _) = list.get_selected_entry().unwrap();
+        assert_eq!(name, "entry2");
+    }
+
+    #[test]
+    fn test_filter_with_no_matches_clears_selection() {
+        let mut list = ResultList::default();
+        list.add_entry(FileEntry::new("entry1", vec![Match::new(0, "apple")]));
+
+        list.set_filter("nonexistent");
+
+        assert_eq!(list.state.selected(), None);
+        assert_eq!(list.get_number_of_matches(), 0);
+    }
+
+    #[test]
+    fn test_get_selected_spans_highlights_current_match() {
+        let mut list = ResultList::default();
+        list.add_entry(FileEntry::new("main.rs", vec![Match::new(0, "fn main() {}")]));
+
+        let spans = list.get_selected_spans().unwrap();
+        let text: String = spans.iter().map(|(_, part)| part.as_str()).collect();
+        assert_eq!(text, "fn main() {}");
+    }
+
+    #[test]
+    fn test_iter_spans_covers_every_entry() {
+        let mut list = ResultList::default();
+        list.add_entry(FileEntry::new("main.rs", vec![Match::new(0, "fn main() {}")]));
+
+        assert_eq!(list.iter_spans().len(), list.entries.len());
+    }
+
+    #[test]
+    fn test_file_watching_splices_removal_on_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("watched.txt");
+        fs::write(&file_path, "hello").unwrap();
+        let header = file_path.to_str().unwrap().to_owned();
+
+        let mut list = ResultList::default();
+        list.enable_file_watching().unwrap();
+        list.add_entry(FileEntry::new(&header, vec![Match::new(0, "hello")]));
+
+        fs::remove_file(&file_path).unwrap();
+        sleep(Duration::from_millis(250));
+
+        for change in list.poll_file_changes() {
+            list.apply_file_change(change, None);
+        }
+
+        assert!(list.is_empty());
+    }
 }
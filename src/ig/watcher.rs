@@ -0,0 +1,163 @@
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+pub enum FileChange {
+    Modified(String),
+    Removed(String),
+}
+
+pub struct FileWatcher {
+    watcher: RecommendedWatcher,
+    receiver: Receiver<(Instant, notify::Result<notify::Event>)>,
+    watched: HashMap<PathBuf, String>,
+    pending: HashMap<String, Instant>,
+    debounce: Duration,
+}
+
+impl FileWatcher {
+    pub fn new() -> notify::Result<Self> {
+        let (sender, receiver) = mpsc::channel();
+        let watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let _ = sender.send((Instant::now(), event));
+            })?;
+
+        Ok(Self {
+            watcher,
+            receiver,
+            watched: HashMap::new(),
+            pending: HashMap::new(),
+            debounce: Duration::from_millis(200),
+        })
+    }
+
+    pub fn watch(&mut self, header: &str) -> notify::Result<()> {
+        let path = Path::new(header);
+        self.watcher.watch(path, RecursiveMode::NonRecursive)?;
+        self.watched.insert(path.to_path_buf(), header.to_owned());
+        Ok(())
+    }
+
+    pub fn unwatch(&mut self, header: &str) -> notify::Result<()> {
+        let path = match self
+            .watched
+            .iter()
+            .find(|(_, watched_header)| watched_header.as_str() == header)
+            .map(|(path, _)| path.clone())
+        {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        self.watcher.unwatch(&path)?;
+        self.watched.remove(&path);
+        self.pending.remove(header);
+        Ok(())
+    }
+
+    pub fn poll(&mut self) -> Vec<FileChange> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok((seen_at, Ok(event))) => {
+                    for path in &event.paths {
+                        if let Some(header) = self.watched.get(path) {
+                            self.pending.insert(header.clone(), seen_at);
+                        }
+                    }
+                }
+                Ok((_, Err(_))) => continue,
+                Err(_) => break,
+            }
+        }
+
+        let now = Instant::now();
+        let ready: Vec<String> = self
+            .pending
+            .iter()
+            .filter(|(_, &seen_at)| now.duration_since(seen_at) >= self.debounce)
+            .map(|(header, _)| header.clone())
+            .collect();
+
+        ready
+            .into_iter()
+            .map(|header| {
+                self.pending.remove(&header);
+                if Path::new(&header).exists() {
+                    FileChange::Modified(header)
+                } else {
+                    FileChange::Removed(header)
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::thread::sleep;
+
+    fn wait_for_debounce() {
+        sleep(Duration::from_millis(250));
+    }
+
+    #[test]
+    fn test_poll_reports_modified_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("a.txt");
+        fs::write(&file_path, "hello").unwrap();
+        let header = file_path.to_str().unwrap().to_owned();
+
+        let mut watcher = FileWatcher::new().unwrap();
+        watcher.watch(&header).unwrap();
+
+        fs::write(&file_path, "hello again").unwrap();
+        wait_for_debounce();
+
+        let changes = watcher.poll();
+        assert!(changes
+            .iter()
+            .any(|change| matches!(change, FileChange::Modified(h) if h == &header)));
+    }
+
+    #[test]
+    fn test_poll_reports_removed_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("b.txt");
+        fs::write(&file_path, "hello").unwrap();
+        let header = file_path.to_str().unwrap().to_owned();
+
+        let mut watcher = FileWatcher::new().unwrap();
+        watcher.watch(&header).unwrap();
+
+        fs::remove_file(&file_path).unwrap();
+        wait_for_debounce();
+
+        let changes = watcher.poll();
+        assert!(changes
+            .iter()
+            .any(|change| matches!(change, FileChange::Removed(h) if h == &header)));
+    }
+
+    #[test]
+    fn test_unwatch_stops_reporting_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join("c.txt");
+        fs::write(&file_path, "hello").unwrap();
+        let header = file_path.to_str().unwrap().to_owned();
+
+        let mut watcher = FileWatcher::new().unwrap();
+        watcher.watch(&header).unwrap();
+        watcher.unwatch(&header).unwrap();
+
+        fs::write(&file_path, "hello again").unwrap();
+        wait_for_debounce();
+
+        assert!(watcher.poll().is_empty());
+    }
+}
+